@@ -0,0 +1,53 @@
+//! Span-insensitive AST comparison.
+//!
+//! `SourceFile`'s derived `PartialEq` compares spans too, which makes it useless for comparing an
+//! AST parsed from source against one built by hand (or parsed a second time after a refactor) --
+//! the spans will essentially never match. `normalize_spans` rewrites every span in a tree to a
+//! single sentinel value using the `Fold` framework, so `eq_ignore_span` (and the
+//! `assert_eq_ignore_span!` macro below) can fall back on the ordinary derived `PartialEq` once
+//! spans are out of the picture.
+
+use ast::fold::Fold;
+use ast::SourceFile;
+use pos::Span;
+
+/// The span every node is rewritten to before comparison.
+pub const SENTINEL_SPAN: Span = Span { lo: 0, hi: 0 };
+
+struct ZeroSpans;
+
+impl Fold for ZeroSpans {
+    fn fold_span(&mut self, _span: Span) -> Span {
+        SENTINEL_SPAN
+    }
+}
+
+/// Rewrite every span in `file` to `SENTINEL_SPAN`.
+pub fn normalize_spans(file: SourceFile) -> SourceFile {
+    ZeroSpans.fold_source_file(file)
+}
+
+/// Compare two `SourceFile`s, ignoring source spans.
+pub fn eq_ignore_span(a: &SourceFile, b: &SourceFile) -> bool {
+    normalize_spans(a.clone()) == normalize_spans(b.clone())
+}
+
+/// Like `assert_eq!`, but compares `ast::SourceFile`s with `eq_ignore_span` instead of
+/// `PartialEq`, and renders both sides with spans zeroed out on failure so the diff isn't
+/// swamped by byte offsets.
+#[macro_export]
+macro_rules! assert_eq_ignore_span {
+    ($left:expr, $right:expr) => {
+        {
+            let left_val = &$left;
+            let right_val = &$right;
+            if !$crate::ast::eq::eq_ignore_span(left_val, right_val) {
+                panic!(
+                    "assertion failed: `(left == right)` (ignoring spans)\n  left: `{:#?}`,\n right: `{:#?}`",
+                    $crate::ast::eq::normalize_spans(left_val.clone()),
+                    $crate::ast::eq::normalize_spans(right_val.clone())
+                );
+            }
+        }
+    };
+}
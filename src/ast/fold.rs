@@ -0,0 +1,259 @@
+//! An owning, rewriting traversal over the AST.
+//!
+//! Unlike `Visit`/`VisitMut`, a `Fold` method consumes a node and returns a (possibly different)
+//! one of the same type. Each `fold_*` method for a node with children defaults to calling the
+//! matching free `fold_*` function, which rebuilds the node from its folded children -- override a
+//! single method to rewrite just that node kind (constant folding, import-alias rewriting, ...)
+//! without having to reimplement reconstruction for everything around it. Node kinds with no
+//! children to fold (the empty placeholder structs in `ast`'s "Unimplemented types" section,
+//! `MaybeQualifiedIdent`, and so on) have no free function; their method returns the node unchanged.
+//!
+//! Every node's `span` is threaded through `fold_span`, so a `Fold` that only overrides
+//! `fold_span` can normalize every span in a tree in one pass -- which is exactly how
+//! `ast::eq::eq_ignore_span` (see there) compares two ASTs while disregarding source positions.
+
+use ast::*;
+use pos::Span;
+
+pub trait Fold: Sized {
+    fn fold_span(&mut self, span: Span) -> Span {
+        span
+    }
+    fn fold_source_file(&mut self, n: SourceFile) -> SourceFile {
+        fold_source_file(self, n)
+    }
+    fn fold_import_decl(&mut self, n: ImportDecl) -> ImportDecl {
+        fold_import_decl(self, n)
+    }
+    fn fold_import_spec(&mut self, n: ImportSpec) -> ImportSpec {
+        fold_import_spec(self, n)
+    }
+    fn fold_top_level_decl(&mut self, n: TopLevelDecl) -> TopLevelDecl {
+        fold_top_level_decl(self, n)
+    }
+    fn fold_decl_statement(&mut self, n: DeclStatement) -> DeclStatement {
+        fold_decl_statement(self, n)
+    }
+    fn fold_const_decl(&mut self, n: ConstDecl) -> ConstDecl {
+        n
+    }
+    fn fold_const_spec(&mut self, n: ConstSpec) -> ConstSpec {
+        fold_const_spec(self, n)
+    }
+    fn fold_type_decl(&mut self, n: TypeDecl) -> TypeDecl {
+        n
+    }
+    fn fold_var_decl(&mut self, n: VarDecl) -> VarDecl {
+        n
+    }
+    fn fold_identifier(&mut self, n: Identifier) -> Identifier {
+        n
+    }
+    fn fold_func_decl(&mut self, n: FuncDecl) -> FuncDecl {
+        fold_func_decl(self, n)
+    }
+    fn fold_method_decl(&mut self, n: MethodDecl) -> MethodDecl {
+        n
+    }
+    fn fold_func_signature(&mut self, n: FuncSignature) -> FuncSignature {
+        fold_func_signature(self, n)
+    }
+    fn fold_parameters(&mut self, n: Parameters) -> Parameters {
+        fold_parameters(self, n)
+    }
+    fn fold_parameter_decl(&mut self, n: ParameterDecl) -> ParameterDecl {
+        fold_parameter_decl(self, n)
+    }
+    fn fold_statement(&mut self, n: Statement) -> Statement {
+        n
+    }
+    fn fold_expression(&mut self, n: Expression) -> Expression {
+        fold_expression(self, n)
+    }
+    fn fold_unary_expr(&mut self, n: UnaryExpr) -> UnaryExpr {
+        fold_unary_expr(self, n)
+    }
+    fn fold_primary_expr(&mut self, n: PrimaryExpr) -> PrimaryExpr {
+        fold_primary_expr(self, n)
+    }
+    fn fold_operand(&mut self, n: Operand) -> Operand {
+        n
+    }
+    fn fold_conversion(&mut self, n: Conversion) -> Conversion {
+        n
+    }
+    fn fold_argument(&mut self, n: Argument) -> Argument {
+        n
+    }
+    fn fold_slice(&mut self, n: Slice) -> Slice {
+        n
+    }
+    fn fold_type(&mut self, n: Type) -> Type {
+        fold_type(self, n)
+    }
+    fn fold_type_literal(&mut self, n: TypeLiteral) -> TypeLiteral {
+        fold_type_literal(self, n)
+    }
+    fn fold_maybe_qualified_ident(&mut self, n: MaybeQualifiedIdent) -> MaybeQualifiedIdent {
+        n
+    }
+    fn fold_array_type(&mut self, n: ArrayType) -> ArrayType {
+        n
+    }
+    fn fold_struct_type(&mut self, n: StructType) -> StructType {
+        n
+    }
+    fn fold_pointer_type(&mut self, n: PointerType) -> PointerType {
+        n
+    }
+    fn fold_func_type(&mut self, n: FuncType) -> FuncType {
+        n
+    }
+    fn fold_interface_type(&mut self, n: InterfaceType) -> InterfaceType {
+        n
+    }
+    fn fold_slice_type(&mut self, n: SliceType) -> SliceType {
+        n
+    }
+    fn fold_map_type(&mut self, n: MapType) -> MapType {
+        n
+    }
+    fn fold_chan_type(&mut self, n: ChanType) -> ChanType {
+        n
+    }
+}
+
+pub fn fold_source_file<F: Fold + ?Sized>(f: &mut F, n: SourceFile) -> SourceFile {
+    SourceFile {
+        package: n.package,
+        import_decls: n.import_decls.into_iter().map(|d| f.fold_import_decl(d)).collect(),
+        top_level_decls: n.top_level_decls.into_iter().map(|d| f.fold_top_level_decl(d)).collect(),
+        span: f.fold_span(n.span),
+    }
+}
+
+pub fn fold_import_decl<F: Fold + ?Sized>(f: &mut F, n: ImportDecl) -> ImportDecl {
+    ImportDecl {
+        specs: n.specs.into_iter().map(|s| f.fold_import_spec(s)).collect(),
+        span: f.fold_span(n.span),
+    }
+}
+
+pub fn fold_import_spec<F: Fold + ?Sized>(f: &mut F, n: ImportSpec) -> ImportSpec {
+    ImportSpec {
+        kind: n.kind,
+        path: n.path,
+        span: f.fold_span(n.span),
+    }
+}
+
+pub fn fold_top_level_decl<F: Fold + ?Sized>(f: &mut F, n: TopLevelDecl) -> TopLevelDecl {
+    match n {
+        TopLevelDecl::Statement(decl) => TopLevelDecl::Statement(f.fold_decl_statement(decl)),
+        TopLevelDecl::Func(decl) => TopLevelDecl::Func(f.fold_func_decl(decl)),
+        TopLevelDecl::Method(decl) => TopLevelDecl::Method(f.fold_method_decl(decl)),
+    }
+}
+
+pub fn fold_decl_statement<F: Fold + ?Sized>(f: &mut F, n: DeclStatement) -> DeclStatement {
+    match n {
+        DeclStatement::Const(decl) => DeclStatement::Const(f.fold_const_decl(decl)),
+        DeclStatement::TypeDecl(decl) => DeclStatement::TypeDecl(f.fold_type_decl(decl)),
+        DeclStatement::VarDecl(decl) => DeclStatement::VarDecl(f.fold_var_decl(decl)),
+    }
+}
+
+pub fn fold_const_spec<F: Fold + ?Sized>(f: &mut F, n: ConstSpec) -> ConstSpec {
+    ConstSpec {
+        identifiers: n.identifiers.into_iter().map(|i| f.fold_identifier(i)).collect(),
+        typ: n.typ.map(|t| f.fold_type(t)),
+        expressions: n.expressions.into_iter().map(|e| f.fold_expression(e)).collect(),
+    }
+}
+
+pub fn fold_func_decl<F: Fold + ?Sized>(f: &mut F, n: FuncDecl) -> FuncDecl {
+    FuncDecl {
+        name: n.name,
+        signature: f.fold_func_signature(n.signature),
+        body: n.body.into_iter().map(|s| f.fold_statement(s)).collect(),
+        span: f.fold_span(n.span),
+    }
+}
+
+pub fn fold_func_signature<F: Fold + ?Sized>(f: &mut F, n: FuncSignature) -> FuncSignature {
+    FuncSignature {
+        parameters: f.fold_parameters(n.parameters),
+        result: f.fold_parameters(n.result),
+    }
+}
+
+pub fn fold_parameters<F: Fold + ?Sized>(f: &mut F, n: Parameters) -> Parameters {
+    Parameters { decls: n.decls.into_iter().map(|d| f.fold_parameter_decl(d)).collect() }
+}
+
+pub fn fold_parameter_decl<F: Fold + ?Sized>(f: &mut F, n: ParameterDecl) -> ParameterDecl {
+    ParameterDecl {
+        identifiers: n.identifiers,
+        typ: f.fold_type(n.typ),
+    }
+}
+
+pub fn fold_expression<F: Fold + ?Sized>(f: &mut F, n: Expression) -> Expression {
+    let kind = match n.kind {
+        ExpressionKind::UnaryExpr(expr) => ExpressionKind::UnaryExpr(f.fold_unary_expr(expr)),
+    };
+    Expression {
+        kind: kind,
+        span: f.fold_span(n.span),
+    }
+}
+
+pub fn fold_unary_expr<F: Fold + ?Sized>(f: &mut F, n: UnaryExpr) -> UnaryExpr {
+    match n {
+        UnaryExpr::PrimaryExpr(expr) => UnaryExpr::PrimaryExpr(Box::new(f.fold_primary_expr(*expr))),
+        UnaryExpr::UnaryOperation(op, expr) => UnaryExpr::UnaryOperation(op, Box::new(f.fold_unary_expr(*expr))),
+    }
+}
+
+pub fn fold_primary_expr<F: Fold + ?Sized>(f: &mut F, n: PrimaryExpr) -> PrimaryExpr {
+    match n {
+        PrimaryExpr::Operand(operand) => PrimaryExpr::Operand(f.fold_operand(operand)),
+        PrimaryExpr::Conversion(conversion) => PrimaryExpr::Conversion(f.fold_conversion(conversion)),
+        PrimaryExpr::Selection(expr, name) => {
+            PrimaryExpr::Selection(Box::new(f.fold_primary_expr(*expr)), name)
+        }
+        PrimaryExpr::Indexing(expr, index) => {
+            PrimaryExpr::Indexing(Box::new(f.fold_primary_expr(*expr)), f.fold_expression(index))
+        }
+        PrimaryExpr::Slicing(expr, slice) => {
+            PrimaryExpr::Slicing(Box::new(f.fold_primary_expr(*expr)), f.fold_slice(slice))
+        }
+        PrimaryExpr::TypeAssertion(expr, name) => {
+            PrimaryExpr::TypeAssertion(Box::new(f.fold_primary_expr(*expr)), name)
+        }
+        PrimaryExpr::FunctionCall(expr, args) => {
+            PrimaryExpr::FunctionCall(Box::new(f.fold_primary_expr(*expr)),
+                                      args.into_iter().map(|a| f.fold_argument(a)).collect())
+        }
+    }
+}
+
+pub fn fold_type<F: Fold + ?Sized>(f: &mut F, n: Type) -> Type {
+    match n {
+        Type::Plain(ident) => Type::Plain(f.fold_maybe_qualified_ident(ident)),
+        Type::Literal(lit) => Type::Literal(f.fold_type_literal(lit)),
+    }
+}
+
+pub fn fold_type_literal<F: Fold + ?Sized>(f: &mut F, n: TypeLiteral) -> TypeLiteral {
+    match n {
+        TypeLiteral::Array(t) => TypeLiteral::Array(f.fold_array_type(t)),
+        TypeLiteral::Struct(t) => TypeLiteral::Struct(f.fold_struct_type(t)),
+        TypeLiteral::Pointer(t) => TypeLiteral::Pointer(f.fold_pointer_type(t)),
+        TypeLiteral::Function(t) => TypeLiteral::Function(f.fold_func_type(t)),
+        TypeLiteral::Interface(t) => TypeLiteral::Interface(f.fold_interface_type(t)),
+        TypeLiteral::Slice(t) => TypeLiteral::Slice(f.fold_slice_type(t)),
+        TypeLiteral::Map(t) => TypeLiteral::Map(f.fold_map_type(t)),
+        TypeLiteral::Chan(t) => TypeLiteral::Chan(f.fold_chan_type(t)),
+    }
+}
@@ -1,5 +1,11 @@
 // Go language specification: https://golang.org/ref/spec
 
+use pos::Span;
+
+pub mod visit;
+pub mod fold;
+pub mod eq;
+
 // SourceFile       = PackageClause ";" { ImportDecl ";" } { TopLevelDecl ";" } .
 
 /// A complete source file.
@@ -8,6 +14,7 @@ pub struct SourceFile {
     pub package: String,
     pub import_decls: Vec<ImportDecl>,
     pub top_level_decls: Vec<TopLevelDecl>,
+    pub span: Span,
 }
 
 // ImportDecl       = "import" ( ImportSpec | "(" { ImportSpec ";" } ")" ) .
@@ -28,6 +35,7 @@ pub struct SourceFile {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ImportDecl {
     pub specs: Vec<ImportSpec>,
+    pub span: Span,
 }
 
 /// An import spec.
@@ -39,6 +47,7 @@ pub struct ImportDecl {
 pub struct ImportSpec {
     pub kind: ImportKind,
     pub path: String,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -94,8 +103,15 @@ pub struct ConstSpec {
 //
 // unary_op   = "+" | "-" | "!" | "^" | "*" | "&" | "<-" .
 
+/// A fully parsed expression, together with the span of source text it came from.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Expression {
+pub struct Expression {
+    pub kind: ExpressionKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpressionKind {
     UnaryExpr(UnaryExpr),
 }
 
@@ -139,10 +155,6 @@ pub enum PrimaryExpr {
     FunctionCall(Box<PrimaryExpr>, Vec<Argument>),
 }
 
-pub fn parse_primary_expr(s: &str) -> PrimaryExpr {
-    unimplemented!()
-}
-
 // Represents a slicing operating... [1:54] for ex
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Slice {
@@ -163,6 +175,7 @@ pub struct FuncDecl {
     pub name: String,
     pub signature: FuncSignature,
     pub body: Vec<Statement>,
+    pub span: Span,
 }
 
 
@@ -195,6 +208,11 @@ impl Parameters {
                         }],
         }
     }
+
+    /// Create a parameter list from already-built parameter declarations.
+    pub fn from_decls(decls: Vec<ParameterDecl>) -> Parameters {
+        Parameters { decls: decls }
+    }
 }
 
 // TODO: variadic functions.
@@ -206,6 +224,16 @@ pub struct ParameterDecl {
     typ: Type,
 }
 
+impl ParameterDecl {
+    /// Create a parameter declaration binding `identifiers` to `typ`.
+    pub fn new(identifiers: Vec<String>, typ: Type) -> ParameterDecl {
+        ParameterDecl {
+            identifiers: identifiers,
+            typ: typ,
+        }
+    }
+}
+
 // XXX: types need attention.
 
 #[derive(Debug, Clone, PartialEq, Eq)]
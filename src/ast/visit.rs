@@ -0,0 +1,380 @@
+//! Borrowing traversals over the AST.
+//!
+//! `Visit` and `VisitMut` are the same traversal, generated twice: once through `&self` (for
+//! traversals that don't need to accumulate state, e.g. pretty-printing) and once through
+//! `&mut self` (for traversals that do, e.g. collecting diagnostics or building a symbol table).
+//! Each `visit_*` method defaults to calling the matching free `walk_*` function, which recurses
+//! into the node's children and calls back into the visitor for each one -- override a single
+//! method to hook just that node kind, the same split rustc's and swc's visitors use.
+//!
+//! Node kinds with no child nodes to recurse into -- `ImportSpec`, `MaybeQualifiedIdent`, and the
+//! empty placeholder structs (see `ast`'s "Unimplemented types" section) -- get a visit method
+//! with a no-op default instead, ready to grow a real walk once the type itself grows fields.
+
+use ast::*;
+
+pub trait Visit<'ast>: Sized {
+    fn visit_source_file(&self, n: &'ast SourceFile) {
+        walk_source_file(self, n);
+    }
+    fn visit_import_decl(&self, n: &'ast ImportDecl) {
+        walk_import_decl(self, n);
+    }
+    fn visit_import_spec(&self, _n: &'ast ImportSpec) {}
+    fn visit_top_level_decl(&self, n: &'ast TopLevelDecl) {
+        walk_top_level_decl(self, n);
+    }
+    fn visit_decl_statement(&self, n: &'ast DeclStatement) {
+        walk_decl_statement(self, n);
+    }
+    fn visit_const_decl(&self, _n: &'ast ConstDecl) {}
+    fn visit_const_spec(&self, n: &'ast ConstSpec) {
+        walk_const_spec(self, n);
+    }
+    fn visit_type_decl(&self, _n: &'ast TypeDecl) {}
+    fn visit_var_decl(&self, _n: &'ast VarDecl) {}
+    fn visit_identifier(&self, _n: &'ast Identifier) {}
+    fn visit_func_decl(&self, n: &'ast FuncDecl) {
+        walk_func_decl(self, n);
+    }
+    fn visit_method_decl(&self, _n: &'ast MethodDecl) {}
+    fn visit_func_signature(&self, n: &'ast FuncSignature) {
+        walk_func_signature(self, n);
+    }
+    fn visit_parameters(&self, n: &'ast Parameters) {
+        walk_parameters(self, n);
+    }
+    fn visit_parameter_decl(&self, n: &'ast ParameterDecl) {
+        walk_parameter_decl(self, n);
+    }
+    fn visit_statement(&self, _n: &'ast Statement) {}
+    fn visit_expression(&self, n: &'ast Expression) {
+        walk_expression(self, n);
+    }
+    fn visit_unary_expr(&self, n: &'ast UnaryExpr) {
+        walk_unary_expr(self, n);
+    }
+    fn visit_primary_expr(&self, n: &'ast PrimaryExpr) {
+        walk_primary_expr(self, n);
+    }
+    fn visit_operand(&self, _n: &'ast Operand) {}
+    fn visit_conversion(&self, _n: &'ast Conversion) {}
+    fn visit_argument(&self, _n: &'ast Argument) {}
+    fn visit_type(&self, n: &'ast Type) {
+        walk_type(self, n);
+    }
+    fn visit_type_literal(&self, n: &'ast TypeLiteral) {
+        walk_type_literal(self, n);
+    }
+    fn visit_maybe_qualified_ident(&self, _n: &'ast MaybeQualifiedIdent) {}
+    fn visit_array_type(&self, _n: &'ast ArrayType) {}
+    fn visit_struct_type(&self, _n: &'ast StructType) {}
+    fn visit_pointer_type(&self, _n: &'ast PointerType) {}
+    fn visit_func_type(&self, _n: &'ast FuncType) {}
+    fn visit_interface_type(&self, _n: &'ast InterfaceType) {}
+    fn visit_slice_type(&self, _n: &'ast SliceType) {}
+    fn visit_map_type(&self, _n: &'ast MapType) {}
+    fn visit_chan_type(&self, _n: &'ast ChanType) {}
+}
+
+pub fn walk_source_file<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast SourceFile) {
+    for import in &n.import_decls {
+        v.visit_import_decl(import);
+    }
+    for decl in &n.top_level_decls {
+        v.visit_top_level_decl(decl);
+    }
+}
+
+pub fn walk_import_decl<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast ImportDecl) {
+    for spec in &n.specs {
+        v.visit_import_spec(spec);
+    }
+}
+
+pub fn walk_top_level_decl<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast TopLevelDecl) {
+    match *n {
+        TopLevelDecl::Statement(ref decl) => v.visit_decl_statement(decl),
+        TopLevelDecl::Func(ref decl) => v.visit_func_decl(decl),
+        TopLevelDecl::Method(ref decl) => v.visit_method_decl(decl),
+    }
+}
+
+pub fn walk_decl_statement<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast DeclStatement) {
+    match *n {
+        DeclStatement::Const(ref decl) => v.visit_const_decl(decl),
+        DeclStatement::TypeDecl(ref decl) => v.visit_type_decl(decl),
+        DeclStatement::VarDecl(ref decl) => v.visit_var_decl(decl),
+    }
+}
+
+pub fn walk_const_spec<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast ConstSpec) {
+    for ident in &n.identifiers {
+        v.visit_identifier(ident);
+    }
+    if let Some(ref typ) = n.typ {
+        v.visit_type(typ);
+    }
+    for expr in &n.expressions {
+        v.visit_expression(expr);
+    }
+}
+
+pub fn walk_func_decl<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast FuncDecl) {
+    v.visit_func_signature(&n.signature);
+    for stmt in &n.body {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_func_signature<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast FuncSignature) {
+    v.visit_parameters(&n.parameters);
+    v.visit_parameters(&n.result);
+}
+
+pub fn walk_parameters<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast Parameters) {
+    for decl in &n.decls {
+        v.visit_parameter_decl(decl);
+    }
+}
+
+pub fn walk_parameter_decl<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast ParameterDecl) {
+    v.visit_type(&n.typ);
+}
+
+pub fn walk_expression<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast Expression) {
+    match n.kind {
+        ExpressionKind::UnaryExpr(ref expr) => v.visit_unary_expr(expr),
+    }
+}
+
+pub fn walk_unary_expr<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast UnaryExpr) {
+    match *n {
+        UnaryExpr::PrimaryExpr(ref expr) => v.visit_primary_expr(expr),
+        UnaryExpr::UnaryOperation(_, ref expr) => v.visit_unary_expr(expr),
+    }
+}
+
+pub fn walk_primary_expr<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast PrimaryExpr) {
+    match *n {
+        PrimaryExpr::Operand(ref operand) => v.visit_operand(operand),
+        PrimaryExpr::Conversion(ref conversion) => v.visit_conversion(conversion),
+        PrimaryExpr::Selection(ref expr, _) => v.visit_primary_expr(expr),
+        PrimaryExpr::Indexing(ref expr, ref index) => {
+            v.visit_primary_expr(expr);
+            v.visit_expression(index);
+        }
+        PrimaryExpr::Slicing(ref expr, _) => v.visit_primary_expr(expr),
+        PrimaryExpr::TypeAssertion(ref expr, _) => v.visit_primary_expr(expr),
+        PrimaryExpr::FunctionCall(ref expr, ref args) => {
+            v.visit_primary_expr(expr);
+            for arg in args {
+                v.visit_argument(arg);
+            }
+        }
+    }
+}
+
+pub fn walk_type<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast Type) {
+    match *n {
+        Type::Plain(ref ident) => v.visit_maybe_qualified_ident(ident),
+        Type::Literal(ref lit) => v.visit_type_literal(lit),
+    }
+}
+
+pub fn walk_type_literal<'ast, V: Visit<'ast> + ?Sized>(v: &V, n: &'ast TypeLiteral) {
+    match *n {
+        TypeLiteral::Array(ref t) => v.visit_array_type(t),
+        TypeLiteral::Struct(ref t) => v.visit_struct_type(t),
+        TypeLiteral::Pointer(ref t) => v.visit_pointer_type(t),
+        TypeLiteral::Function(ref t) => v.visit_func_type(t),
+        TypeLiteral::Interface(ref t) => v.visit_interface_type(t),
+        TypeLiteral::Slice(ref t) => v.visit_slice_type(t),
+        TypeLiteral::Map(ref t) => v.visit_map_type(t),
+        TypeLiteral::Chan(ref t) => v.visit_chan_type(t),
+    }
+}
+
+/// The `&mut self` counterpart to `Visit`, for traversals that need to accumulate state (a set of
+/// visited names, a list of diagnostics, ...) as they go.
+pub trait VisitMut<'ast>: Sized {
+    fn visit_source_file(&mut self, n: &'ast SourceFile) {
+        walk_source_file_mut(self, n);
+    }
+    fn visit_import_decl(&mut self, n: &'ast ImportDecl) {
+        walk_import_decl_mut(self, n);
+    }
+    fn visit_import_spec(&mut self, _n: &'ast ImportSpec) {}
+    fn visit_top_level_decl(&mut self, n: &'ast TopLevelDecl) {
+        walk_top_level_decl_mut(self, n);
+    }
+    fn visit_decl_statement(&mut self, n: &'ast DeclStatement) {
+        walk_decl_statement_mut(self, n);
+    }
+    fn visit_const_decl(&mut self, _n: &'ast ConstDecl) {}
+    fn visit_const_spec(&mut self, n: &'ast ConstSpec) {
+        walk_const_spec_mut(self, n);
+    }
+    fn visit_type_decl(&mut self, _n: &'ast TypeDecl) {}
+    fn visit_var_decl(&mut self, _n: &'ast VarDecl) {}
+    fn visit_identifier(&mut self, _n: &'ast Identifier) {}
+    fn visit_func_decl(&mut self, n: &'ast FuncDecl) {
+        walk_func_decl_mut(self, n);
+    }
+    fn visit_method_decl(&mut self, _n: &'ast MethodDecl) {}
+    fn visit_func_signature(&mut self, n: &'ast FuncSignature) {
+        walk_func_signature_mut(self, n);
+    }
+    fn visit_parameters(&mut self, n: &'ast Parameters) {
+        walk_parameters_mut(self, n);
+    }
+    fn visit_parameter_decl(&mut self, n: &'ast ParameterDecl) {
+        walk_parameter_decl_mut(self, n);
+    }
+    fn visit_statement(&mut self, _n: &'ast Statement) {}
+    fn visit_expression(&mut self, n: &'ast Expression) {
+        walk_expression_mut(self, n);
+    }
+    fn visit_unary_expr(&mut self, n: &'ast UnaryExpr) {
+        walk_unary_expr_mut(self, n);
+    }
+    fn visit_primary_expr(&mut self, n: &'ast PrimaryExpr) {
+        walk_primary_expr_mut(self, n);
+    }
+    fn visit_operand(&mut self, _n: &'ast Operand) {}
+    fn visit_conversion(&mut self, _n: &'ast Conversion) {}
+    fn visit_argument(&mut self, _n: &'ast Argument) {}
+    fn visit_type(&mut self, n: &'ast Type) {
+        walk_type_mut(self, n);
+    }
+    fn visit_type_literal(&mut self, n: &'ast TypeLiteral) {
+        walk_type_literal_mut(self, n);
+    }
+    fn visit_maybe_qualified_ident(&mut self, _n: &'ast MaybeQualifiedIdent) {}
+    fn visit_array_type(&mut self, _n: &'ast ArrayType) {}
+    fn visit_struct_type(&mut self, _n: &'ast StructType) {}
+    fn visit_pointer_type(&mut self, _n: &'ast PointerType) {}
+    fn visit_func_type(&mut self, _n: &'ast FuncType) {}
+    fn visit_interface_type(&mut self, _n: &'ast InterfaceType) {}
+    fn visit_slice_type(&mut self, _n: &'ast SliceType) {}
+    fn visit_map_type(&mut self, _n: &'ast MapType) {}
+    fn visit_chan_type(&mut self, _n: &'ast ChanType) {}
+}
+
+pub fn walk_source_file_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast SourceFile) {
+    for import in &n.import_decls {
+        v.visit_import_decl(import);
+    }
+    for decl in &n.top_level_decls {
+        v.visit_top_level_decl(decl);
+    }
+}
+
+pub fn walk_import_decl_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast ImportDecl) {
+    for spec in &n.specs {
+        v.visit_import_spec(spec);
+    }
+}
+
+pub fn walk_top_level_decl_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast TopLevelDecl) {
+    match *n {
+        TopLevelDecl::Statement(ref decl) => v.visit_decl_statement(decl),
+        TopLevelDecl::Func(ref decl) => v.visit_func_decl(decl),
+        TopLevelDecl::Method(ref decl) => v.visit_method_decl(decl),
+    }
+}
+
+pub fn walk_decl_statement_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast DeclStatement) {
+    match *n {
+        DeclStatement::Const(ref decl) => v.visit_const_decl(decl),
+        DeclStatement::TypeDecl(ref decl) => v.visit_type_decl(decl),
+        DeclStatement::VarDecl(ref decl) => v.visit_var_decl(decl),
+    }
+}
+
+pub fn walk_const_spec_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast ConstSpec) {
+    for ident in &n.identifiers {
+        v.visit_identifier(ident);
+    }
+    if let Some(ref typ) = n.typ {
+        v.visit_type(typ);
+    }
+    for expr in &n.expressions {
+        v.visit_expression(expr);
+    }
+}
+
+pub fn walk_func_decl_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast FuncDecl) {
+    v.visit_func_signature(&n.signature);
+    for stmt in &n.body {
+        v.visit_statement(stmt);
+    }
+}
+
+pub fn walk_func_signature_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast FuncSignature) {
+    v.visit_parameters(&n.parameters);
+    v.visit_parameters(&n.result);
+}
+
+pub fn walk_parameters_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast Parameters) {
+    for decl in &n.decls {
+        v.visit_parameter_decl(decl);
+    }
+}
+
+pub fn walk_parameter_decl_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast ParameterDecl) {
+    v.visit_type(&n.typ);
+}
+
+pub fn walk_expression_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast Expression) {
+    match n.kind {
+        ExpressionKind::UnaryExpr(ref expr) => v.visit_unary_expr(expr),
+    }
+}
+
+pub fn walk_unary_expr_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast UnaryExpr) {
+    match *n {
+        UnaryExpr::PrimaryExpr(ref expr) => v.visit_primary_expr(expr),
+        UnaryExpr::UnaryOperation(_, ref expr) => v.visit_unary_expr(expr),
+    }
+}
+
+pub fn walk_primary_expr_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast PrimaryExpr) {
+    match *n {
+        PrimaryExpr::Operand(ref operand) => v.visit_operand(operand),
+        PrimaryExpr::Conversion(ref conversion) => v.visit_conversion(conversion),
+        PrimaryExpr::Selection(ref expr, _) => v.visit_primary_expr(expr),
+        PrimaryExpr::Indexing(ref expr, ref index) => {
+            v.visit_primary_expr(expr);
+            v.visit_expression(index);
+        }
+        PrimaryExpr::Slicing(ref expr, _) => v.visit_primary_expr(expr),
+        PrimaryExpr::TypeAssertion(ref expr, _) => v.visit_primary_expr(expr),
+        PrimaryExpr::FunctionCall(ref expr, ref args) => {
+            v.visit_primary_expr(expr);
+            for arg in args {
+                v.visit_argument(arg);
+            }
+        }
+    }
+}
+
+pub fn walk_type_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast Type) {
+    match *n {
+        Type::Plain(ref ident) => v.visit_maybe_qualified_ident(ident),
+        Type::Literal(ref lit) => v.visit_type_literal(lit),
+    }
+}
+
+pub fn walk_type_literal_mut<'ast, V: VisitMut<'ast> + ?Sized>(v: &mut V, n: &'ast TypeLiteral) {
+    match *n {
+        TypeLiteral::Array(ref t) => v.visit_array_type(t),
+        TypeLiteral::Struct(ref t) => v.visit_struct_type(t),
+        TypeLiteral::Pointer(ref t) => v.visit_pointer_type(t),
+        TypeLiteral::Function(ref t) => v.visit_func_type(t),
+        TypeLiteral::Interface(ref t) => v.visit_interface_type(t),
+        TypeLiteral::Slice(ref t) => v.visit_slice_type(t),
+        TypeLiteral::Map(ref t) => v.visit_map_type(t),
+        TypeLiteral::Chan(ref t) => v.visit_chan_type(t),
+    }
+}
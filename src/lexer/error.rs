@@ -0,0 +1,78 @@
+//! Lexer errors and a renderer that turns them into rustc-style diagnostics.
+
+use pos::{SourceMap, Span};
+
+quick_error! {
+    /// The different things that can go wrong while scanning a token.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum LexErrorKind {
+        UnexpectedChar(c: char) {
+            display("unexpected character {:?}", c)
+        }
+        UnterminatedString {
+            display("unterminated string literal")
+        }
+        UnterminatedRawString {
+            display("unterminated raw string literal")
+        }
+        UnterminatedRune {
+            display("unterminated rune literal")
+        }
+        InvalidEscape(c: char) {
+            display("invalid escape sequence '\\{}'", c)
+        }
+    }
+}
+
+/// A single lexical error, recorded so scanning can recover and keep going instead of bailing
+/// out of the whole file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub span: Span,
+    pub help: Option<String>,
+}
+
+impl LexError {
+    pub fn new(kind: LexErrorKind, span: Span) -> LexError {
+        LexError {
+            kind: kind,
+            span: span,
+            help: None,
+        }
+    }
+
+    /// Attach a help note, printed below the underlined source line.
+    pub fn with_help(mut self, help: &str) -> LexError {
+        self.help = Some(help.into());
+        self
+    }
+}
+
+/// Render `err` the way rustc/ariadne do: a `file:line:col` header, the offending source line,
+/// and a caret underline spanning the bad range, followed by the help note (if any).
+pub fn render(src: &str, file: &str, err: &LexError) -> String {
+    let map = SourceMap::new(src);
+    let pos = map.position(err.span.lo);
+    let line = map.line_text(src, pos.line);
+
+    let width = if err.span.hi > err.span.lo {
+        err.span.hi - err.span.lo
+    } else {
+        1
+    };
+
+    let mut out = format!("{}:{}:{}: error: {}\n", file, pos.line, pos.column, err.kind);
+    out.push_str(line);
+    out.push('\n');
+    for _ in 0..pos.column - 1 {
+        out.push(' ');
+    }
+    out.push_str(&"^".repeat(width));
+
+    if let Some(ref help) = err.help {
+        out.push_str(&format!("\n  help: {}", help));
+    }
+
+    out
+}
@@ -6,17 +6,23 @@
 //! ## Notes
 //!
 //! We want meaningful errors from the start. That means printing the line and column number on
-//! error, returning `Result`s instead of panicking (later on, we may use unwinding to speed up
-//! lexical analysis in non-erroneous cases).
+//! error, recovering from a bad token instead of panicking, and letting a whole file be scanned
+//! (and reported on) in one pass. `Lexer::into_results` is the entry point for that: it returns
+//! every `Spanned<Token>` it could scan alongside the `LexError`s it recovered from.
 //!
 //! It is unclear whether we should operator on Unicode `char`, or plain bytes `u8`. `char`s are
 //! more convenient to display and offer a clean API; bytes are (most likely) faster to work with.
 
 use std::iter::Iterator;
 
+use pos::{Span, Spanned};
+
 mod token;
 pub use self::token::*;
 
+mod error;
+pub use self::error::*;
+
 #[cfg(test)]
 mod test;
 
@@ -27,25 +33,72 @@ pub struct Lexer<'src> {
     src: &'src str,
     /// The last char that was read.
     current_char: Option<char>,
+    /// The last non-whitespace token that was produced, used to decide whether automatic
+    /// semicolon insertion (ASI) applies at the next newline. `None` before the first token, and
+    /// `Some(Token::Semicolon)` right after an inserted or explicit semicolon.
+    last_significant: Option<Token>,
+    /// A token that has already been scanned but not yet returned, used when ASI needs to
+    /// insert a `Token::Semicolon` ahead of a token we already bumped past.
+    pending: Option<Token>,
+    /// The span of `pending`, kept alongside it since it was computed before the semicolon that
+    /// is returned first.
+    pending_span: Option<Span>,
+    /// The span of the token most recently returned from `next()`, read by the `spanned()`
+    /// adaptor.
+    last_span: Span,
+    /// Errors recovered from so far; see `Lexer::into_results`.
+    errors: Vec<LexError>,
+}
+
+/// The result of decoding a single escape sequence (see `Lexer::scan_escape`): either a full
+/// Unicode code point, or a raw byte that still needs combining with any escapes around it.
+enum Escape {
+    Byte(u8),
+    Char(char),
+}
+
+/// Decode `bytes` as UTF-8 and append the result to `value`, then clear `bytes`. A no-op if
+/// `bytes` is empty. Used to flush a run of `\x`/octal byte escapes once it's interrupted by an
+/// ordinary character or a code-point escape.
+fn flush_byte_escapes(value: &mut String, bytes: &mut Vec<u8>) {
+    if !bytes.is_empty() {
+        value.push_str(&String::from_utf8_lossy(bytes));
+        bytes.clear();
+    }
 }
 
 impl<'src> Lexer<'src> {
     pub fn new(s: &str) -> Lexer {
         let first_char = s.chars().next();
-        println!("first_char: {:?}", first_char);
-        let mut l = Lexer {
+        Lexer {
             src: s,
             pos: 0,
             current_char: first_char, // Ugly?
-        };
+            last_significant: None,
+            pending: None,
+            pending_span: None,
+            last_span: Span::empty(0),
+            errors: Vec::new(),
+        }
+    }
 
-        l
+    /// Scan every token and return it alongside its span, together with any errors recovered
+    /// from along the way. An unexpected character is recorded as a `LexError` and skipped so the
+    /// rest of the file is still scanned; `Iterator::next` skips it the same way, but this is the
+    /// only way to get at the recorded errors.
+    pub fn into_results(mut self) -> (Vec<Spanned<Token>>, Vec<LexError>) {
+        let mut tokens = Vec::new();
+        while let Some(tok) = self.next() {
+            tokens.push(Spanned::new(tok, self.last_span));
+        }
+        (tokens, self.errors)
     }
 
     /// 'eat' one character.
     fn bump(&mut self) {
-        let old = self.current_char;
-        self.pos += 1;
+        // `current_char` may be multiple bytes wide (e.g. 'é' is 2 bytes); advancing by a fixed
+        // 1 would land `pos` in the middle of it and panic the next time we slice `src` there.
+        self.pos += self.current_char.map_or(1, char::len_utf8);
 
         if self.pos < self.src.len() {
             let ch = char_at(&self.src, self.pos);
@@ -55,212 +108,680 @@ impl<'src> Lexer<'src> {
         }
     }
 
-    fn scan_identifier(&mut self) -> String {
-        unimplemented!()
-    }
-}
+    /// Scan an identifier or keyword starting at `start` (the byte offset of its first,
+    /// already-current character) and return its text; the caller matches the result against
+    /// the keyword list to decide which token it is.
+    fn scan_identifier(&mut self, start: usize) -> String {
+        while let Some(c) = self.current_char {
+            if can_continue_identifier(c) {
+                self.bump();
+            } else {
+                break;
+            }
+        }
 
-impl<'src> Iterator for Lexer<'src> {
-    type Item = Token;
+        self.src[start..self.pos].to_string()
+    }
 
-    /// Return the next token, if any.
-    ///
-    /// A fundamental property of this function is that **the next token does not depend on the
-    /// previous one**.
-    /// This means many syntactically incorrect inputs, such as `, , ,` or `;+m/^`, can pass
-    /// tokenization, even though they would fail parsing.
-    /// This also means testing whether a single token is tokenized properly does not require
-    /// scaffolding (i.e. building an entire test program), which is a good thing.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// use rgo::lexer::{Lexer, Token, DelimToken};
+    /// Scan an interpreted string literal (`"..."`), decoding escape sequences as it goes.
+    /// `start` is the byte offset of the opening quote.
     ///
-    /// let mut lexer = Lexer::new(")");
-    /// assert_eq!(lexer.next(), Some(Token::CloseDelim(DelimToken::Paren)));
-    /// ```
-    fn next(&mut self) -> Option<Token> {
-        // Stop tokenizing on EOF.
-        let c = match self.current_char {
-            Some(c) => c,
-            None => return None,
+    /// `\x` and octal escapes denote raw bytes, not code points (see `Escape`), so they're
+    /// buffered in `pending_bytes` and decoded together as UTF-8 once a non-byte escape or
+    /// ordinary character breaks the run -- otherwise e.g. `"\xc3\xa9"` would decode as the two
+    /// unrelated code points U+00C3 and U+00A9 instead of the 'é' their bytes jointly encode.
+    fn scan_interpreted_string(&mut self, start: usize) -> Token {
+        self.bump(); // opening quote
+
+        let mut value = String::new();
+        let mut pending_bytes: Vec<u8> = Vec::new();
+
+        loop {
+            match self.current_char {
+                Some('"') => {
+                    self.bump();
+                    break;
+                }
+                Some('\\') => {
+                    let esc_start = self.pos;
+                    self.bump();
+                    match self.scan_escape(esc_start) {
+                        Escape::Byte(b) => pending_bytes.push(b),
+                        Escape::Char(c) => {
+                            flush_byte_escapes(&mut value, &mut pending_bytes);
+                            value.push(c);
+                        }
+                    }
+                }
+                Some('\n') | None => {
+                    flush_byte_escapes(&mut value, &mut pending_bytes);
+                    self.errors.push(LexError::new(LexErrorKind::UnterminatedString,
+                                                     Span::new(start, self.pos))
+                        .with_help("add a closing '\"'"));
+                    break;
+                }
+                Some(c) => {
+                    flush_byte_escapes(&mut value, &mut pending_bytes);
+                    value.push(c);
+                    self.bump();
+                }
+            }
+        }
+
+        flush_byte_escapes(&mut value, &mut pending_bytes);
+
+        Token::Literal(Literal::Str(value))
+    }
+
+    /// Scan a raw string literal (`` `...` ``). No escapes are recognised and newlines are kept
+    /// as-is, except that carriage returns are discarded (as the Go spec requires).
+    fn scan_raw_string(&mut self, start: usize) -> Token {
+        self.bump(); // opening backtick
+        let text_start = self.pos;
+
+        while let Some(c) = self.current_char {
+            if c == '`' {
+                break;
+            }
+            self.bump();
+        }
+
+        let value = self.src[text_start..self.pos].replace('\r', "");
+
+        if self.current_char.is_none() {
+            self.errors.push(LexError::new(LexErrorKind::UnterminatedRawString,
+                                             Span::new(start, self.pos))
+                .with_help("add a closing '`'"));
+        } else {
+            self.bump();
+        }
+
+        Token::Literal(Literal::Str(value))
+    }
+
+    /// Scan a rune literal (`'a'`, `'\n'`, `'é'`).
+    fn scan_rune(&mut self, start: usize) -> Token {
+        self.bump(); // opening quote
+
+        let value = match self.current_char {
+            Some('\\') => {
+                let esc_start = self.pos;
+                self.bump();
+                match self.scan_escape(esc_start) {
+                    // A byte value in the range 0-255 is always a valid Unicode scalar, so this
+                    // never loses information -- see `Escape`.
+                    Escape::Byte(b) => b as char,
+                    Escape::Char(c) => c,
+                }
+            }
+            Some(c) => {
+                self.bump();
+                c
+            }
+            None => {
+                self.errors.push(LexError::new(LexErrorKind::UnterminatedRune,
+                                                 Span::new(start, self.pos))
+                    .with_help("add a closing \"'\""));
+                '\u{fffd}'
+            }
         };
 
-        let tok = match c {
-            // Single-character tokens.
-            '(' => {
+        match self.current_char {
+            Some('\'') => {
                 self.bump();
-                Token::OpenDelim(DelimToken::Paren)
             }
-            ')' => {
+            _ => {
+                self.errors.push(LexError::new(LexErrorKind::UnterminatedRune,
+                                                 Span::new(start, self.pos))
+                    .with_help("a rune literal holds exactly one character"));
+            }
+        }
+
+        Token::Literal(Literal::Rune(value))
+    }
+
+    /// Decode the escape sequence starting right after the `'\\'` at `start`, leaving
+    /// `current_char` positioned after it. Reports a `LexError` on anything malformed and still
+    /// returns a value, so the caller never has to bail out: an unknown escape letter yields that
+    /// letter, a short `\x` escape the digits read so far, an out-of-range octal escape `0xff`, and
+    /// a short or invalid `\u`/`\U` escape (or a `'\\'` at end of input) the Unicode replacement
+    /// character.
+    ///
+    /// `\x` and octal escapes decode to a raw byte rather than a `char`: per the Go spec they're
+    /// only guaranteed to be a whole code point on their own inside a rune literal (where a
+    /// single byte 0-255 is always valid Unicode), but inside a string literal several of them in
+    /// a row are meant to be concatenated as bytes and interpreted as one UTF-8 sequence -- see
+    /// `scan_interpreted_string`.
+    fn scan_escape(&mut self, start: usize) -> Escape {
+        match self.current_char {
+            Some('n') => {
                 self.bump();
-                Token::CloseDelim(DelimToken::Paren)
+                Escape::Char('\n')
             }
-            '{' => {
+            Some('t') => {
                 self.bump();
-                Token::OpenDelim(DelimToken::Brace)
+                Escape::Char('\t')
             }
-            '}' => {
+            Some('\\') => {
                 self.bump();
-                Token::CloseDelim(DelimToken::Brace)
+                Escape::Char('\\')
             }
-            '[' => {
+            Some('"') => {
                 self.bump();
-                Token::OpenDelim(DelimToken::Bracket)
+                Escape::Char('"')
             }
-            ']' => {
+            Some('\'') => {
                 self.bump();
-                Token::CloseDelim(DelimToken::Bracket)
+                Escape::Char('\'')
             }
-            ',' => {
+            Some('x') => {
                 self.bump();
-                Token::Comma
+                Escape::Byte(self.scan_hex_byte_escape(start))
             }
-            // More complex tokens.
-            '.' => {
+            Some('u') => {
                 self.bump();
-                // FIXME: ellipsis '...'
-                Token::Dot
+                Escape::Char(self.scan_hex_escape(4, start))
             }
-            '+' => {
+            Some('U') => {
                 self.bump();
+                Escape::Char(self.scan_hex_escape(8, start))
+            }
+            Some(c) if c.is_digit(8) => Escape::Byte(self.scan_octal_byte_escape(start)),
+            Some(c) => {
+                self.errors.push(LexError::new(LexErrorKind::InvalidEscape(c),
+                                                 Span::new(start, self.pos + 1))
+                    .with_help("valid escapes are \\n \\t \\\\ \\\" \\xFF \\uXXXX \\UXXXXXXXX, \
+                                and octal \\NNN"));
+                self.bump();
+                Escape::Char(c)
+            }
+            None => {
+                self.errors.push(LexError::new(LexErrorKind::InvalidEscape('\0'),
+                                                 Span::new(start, self.pos))
+                    .with_help("expected an escape sequence after '\\'"));
+                Escape::Char('\u{fffd}')
+            }
+        }
+    }
 
-                match self.current_char {
-                    Some('+') => {
-                        self.bump();
-                        Token::Increment
-                    }
-                    Some('=') => {
-                        self.bump();
-                        Token::PlusEquals
-                    }
-                    _ => Token::Plus,
+    /// Scan exactly `digits` hex digits (`\uXXXX`, `\UXXXXXXXX`) and decode them as a Unicode
+    /// code point.
+    fn scan_hex_escape(&mut self, digits: usize, start: usize) -> char {
+        let letter = if digits == 4 { 'u' } else { 'U' };
+        let mut value: u32 = 0;
+        let mut count = 0;
+
+        while count < digits {
+            match self.current_char.and_then(|c| c.to_digit(16)) {
+                Some(d) => {
+                    value = value * 16 + d;
+                    self.bump();
+                    count += 1;
                 }
+                None => break,
+            }
+        }
+
+        if count < digits {
+            self.errors.push(LexError::new(LexErrorKind::InvalidEscape(letter),
+                                             Span::new(start, self.pos))
+                .with_help(&format!("expected {} hex digits", digits)));
+            return '\u{fffd}';
+        }
+
+        match ::std::char::from_u32(value) {
+            Some(c) => c,
+            None => {
+                self.errors.push(LexError::new(LexErrorKind::InvalidEscape(letter),
+                                                 Span::new(start, self.pos))
+                    .with_help("not a valid Unicode code point"));
+                '\u{fffd}'
             }
-            '-' => {
+        }
+    }
+
+    /// Scan exactly two hex digits (`\xFF`) and return the raw byte they spell out.
+    fn scan_hex_byte_escape(&mut self, start: usize) -> u8 {
+        let mut value: u32 = 0;
+        let mut count = 0;
+
+        while count < 2 {
+            match self.current_char.and_then(|c| c.to_digit(16)) {
+                Some(d) => {
+                    value = value * 16 + d;
+                    self.bump();
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+
+        if count < 2 {
+            self.errors.push(LexError::new(LexErrorKind::InvalidEscape('x'),
+                                             Span::new(start, self.pos))
+                .with_help("expected 2 hex digits"));
+        }
+
+        value as u8
+    }
+
+    /// Scan up to three octal digits (`\377`) and return the raw byte they spell out.
+    fn scan_octal_byte_escape(&mut self, start: usize) -> u8 {
+        let mut value: u32 = 0;
+        let mut count = 0;
+
+        while count < 3 {
+            match self.current_char.and_then(|c| c.to_digit(8)) {
+                Some(d) => {
+                    value = value * 8 + d;
+                    self.bump();
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+
+        if value > 0xff {
+            self.errors.push(LexError::new(LexErrorKind::InvalidEscape('0'),
+                                             Span::new(start, self.pos))
+                .with_help("octal escape value must be between \\000 and \\377"));
+            return 0xff;
+        }
+
+        value as u8
+    }
+
+    /// Scan a numeric literal: a decimal/hex (`0x`)/octal (`0o`/legacy `0NNN`)/binary (`0b`)
+    /// integer, a float with an optional exponent, or either with the imaginary suffix `i`.
+    /// `start` is the byte offset of the first digit.
+    fn scan_number(&mut self, start: usize) -> Token {
+        if self.current_char == Some('0') {
+            self.bump();
+
+            match self.current_char {
+                Some('x') | Some('X') => {
+                    self.bump();
+                    self.scan_digits(|c| c.is_digit(16));
+                    return self.finish_number(start, false);
+                }
+                Some('o') | Some('O') => {
+                    self.bump();
+                    self.scan_digits(|c| c.is_digit(8));
+                    return self.finish_number(start, false);
+                }
+                Some('b') | Some('B') => {
+                    self.bump();
+                    self.scan_digits(|c| c == '0' || c == '1');
+                    return self.finish_number(start, false);
+                }
+                _ => {
+                    // Either a legacy octal literal (`0755`), a lone `0`, or the start of a
+                    // float (`0.5`) -- all are decimal digit sequences as far as scanning goes.
+                }
+            }
+        }
+
+        self.scan_digits(|c| c.is_digit(10));
+
+        let mut is_float = false;
+
+        if self.current_char == Some('.') {
+            is_float = true;
+            self.bump();
+            self.scan_digits(|c| c.is_digit(10));
+        }
+
+        match self.current_char {
+            Some('e') | Some('E') => {
+                is_float = true;
                 self.bump();
 
                 match self.current_char {
-                    Some('-') => {
-                        self.bump();
-                        Token::Decrement
-                    }
-                    Some('=') => {
+                    Some('+') | Some('-') => {
                         self.bump();
-                        Token::MinusEquals
                     }
-                    _ => Token::Minus,
+                    _ => {}
                 }
+
+                self.scan_digits(|c| c.is_digit(10));
             }
-            '|' => {
+            _ => {}
+        }
+
+        self.finish_number(start, is_float)
+    }
+
+    /// Consume a run of digits matching `is_digit`, allowing `_` as a separator.
+    fn scan_digits<F: Fn(char) -> bool>(&mut self, is_digit: F) {
+        while let Some(c) = self.current_char {
+            if is_digit(c) || c == '_' {
                 self.bump();
+            } else {
+                break;
+            }
+        }
+    }
 
-                match self.current_char {
-                    Some('|') => {
-                        self.bump();
-                        Token::PipePipe
+    /// Consume the trailing imaginary suffix `i`, if any, and build the `Literal` for the
+    /// digits scanned from `start`.
+    fn finish_number(&mut self, start: usize, is_float: bool) -> Token {
+        let imaginary = match self.current_char {
+            Some('i') => {
+                self.bump();
+                true
+            }
+            _ => false,
+        };
+
+        let text = self.src[start..self.pos].to_string();
+
+        Token::Literal(if imaginary {
+            Literal::Imaginary(text)
+        } else if is_float {
+            Literal::Float(text)
+        } else {
+            Literal::Int(text)
+        })
+    }
+}
+
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Token;
+
+    /// Return the next token, if any.
+    ///
+    /// Apart from automatic semicolon insertion, which looks at the last significant token (see
+    /// `ends_statement`), **the next token does not depend on the previous one**.
+    /// This means many syntactically incorrect inputs, such as `, , ,` or `;+m`, can pass
+    /// tokenization, even though they would fail parsing.
+    /// This also means testing whether a single token is tokenized properly does not require
+    /// scaffolding (i.e. building an entire test program), which is a good thing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rgo::lexer::{Lexer, Token, DelimToken};
+    ///
+    /// let mut lexer = Lexer::new(")");
+    /// assert_eq!(lexer.next(), Some(Token::CloseDelim(DelimToken::Paren)));
+    /// ```
+    fn next(&mut self) -> Option<Token> {
+        // A token deferred by automatic semicolon insertion (see `'}'` below) takes priority
+        // over scanning anything new.
+        if let Some(tok) = self.pending.take() {
+            self.last_significant = Some(tok.clone());
+            self.last_span = self.pending_span.take().expect("pending token without a span");
+            return Some(tok);
+        }
+
+        // Loop so that recovering from an unexpected character (below) can try scanning again
+        // instead of giving up on the rest of the file.
+        loop {
+            if let Some(semi) = self.skip_whitespace() {
+                self.last_span = Span::empty(self.pos);
+                return Some(semi);
+            }
+
+            // Stop tokenizing on EOF -- unless the last significant token is one ASI would fire
+            // after on a newline (e.g. a file missing its trailing newline), in which case the
+            // end of input counts as the newline that was never there.
+            let c = match self.current_char {
+                Some(c) => c,
+                None => {
+                    if ends_statement(&self.last_significant) {
+                        self.last_significant = Some(Token::Semicolon);
+                        self.last_span = Span::empty(self.pos);
+                        return Some(Token::Semicolon);
                     }
-                    Some('=') => {
-                        self.bump();
-                        Token::PipeEquals
+                    return None;
+                }
+            };
+
+            let start = self.pos;
+
+            let tok = match c {
+                // Single-character tokens.
+                '(' => {
+                    self.bump();
+                    Token::OpenDelim(DelimToken::Paren)
+                }
+                ')' => {
+                    self.bump();
+                    Token::CloseDelim(DelimToken::Paren)
+                }
+                '{' => {
+                    self.bump();
+                    Token::OpenDelim(DelimToken::Brace)
+                }
+                '}' => {
+                    self.bump();
+
+                    // A statement directly followed by '}' gets its closing semicolon inserted too,
+                    // not just one that is followed by a newline.
+                    if ends_statement(&self.last_significant) {
+                        self.pending = Some(Token::CloseDelim(DelimToken::Brace));
+                        self.pending_span = Some(Span::new(start, self.pos));
+                        self.last_significant = Some(Token::Semicolon);
+                        self.last_span = Span::empty(start);
+                        return Some(Token::Semicolon);
                     }
-                    _ => Token::Pipe,
+
+                    Token::CloseDelim(DelimToken::Brace)
                 }
-            }
-            c if can_start_identifier(c) => {
-                let start = self.pos;
-                println!("c: {}", c);
-
-                loop {
-                    if let Some(c) = self.current_char {
-                        println!("ident c: {}", c);
-                        if can_continue_identifier(c) {
+                '[' => {
+                    self.bump();
+                    Token::OpenDelim(DelimToken::Bracket)
+                }
+                ']' => {
+                    self.bump();
+                    Token::CloseDelim(DelimToken::Bracket)
+                }
+                ',' => {
+                    self.bump();
+                    Token::Comma
+                }
+                ';' => {
+                    self.bump();
+                    Token::Semicolon
+                }
+                // More complex tokens.
+                '.' => {
+                    self.bump();
+
+                    if self.current_char == Some('.') {
+                        self.bump();
+
+                        if self.current_char == Some('.') {
                             self.bump();
+                            Token::Ellipsis
                         } else {
-                            break;
+                            self.errors.push(LexError::new(LexErrorKind::UnexpectedChar('.'),
+                                                             Span::new(start, self.pos))
+                                .with_help("'..' is not valid Go syntax; did you mean '...'?"));
+                            Token::Dot
                         }
                     } else {
-                        break;
+                        Token::Dot
                     }
                 }
+                '+' => {
+                    self.bump();
 
-                let ident = &self.src[start..self.pos];
-
-                match &*ident {
-                    "break" => Token::Keyword(Keyword::Break),
-                    "case" => Token::Keyword(Keyword::Case),
-                    "chan" => Token::Keyword(Keyword::Chan),
-                    "const" => Token::Keyword(Keyword::Const),
-                    "continue" => Token::Keyword(Keyword::Continue),
-                    "default" => Token::Keyword(Keyword::Default),
-                    "defer" => Token::Keyword(Keyword::Defer),
-                    "else" => Token::Keyword(Keyword::Else),
-                    "fallthrough" => Token::Keyword(Keyword::Fallthrough),
-                    "for" => Token::Keyword(Keyword::For),
-                    "func" => Token::Keyword(Keyword::Func),
-                    "go" => Token::Keyword(Keyword::Go),
-                    "goto" => Token::Keyword(Keyword::Goto),
-                    "if" => Token::Keyword(Keyword::If),
-                    "import" => Token::Keyword(Keyword::Import),
-                    "interface" => Token::Keyword(Keyword::Interface),
-                    "map" => Token::Keyword(Keyword::Map),
-                    "package" => Token::Keyword(Keyword::Package),
-                    "range" => Token::Keyword(Keyword::Range),
-                    "return" => Token::Keyword(Keyword::Return),
-                    "select" => Token::Keyword(Keyword::Select),
-                    "struct" => Token::Keyword(Keyword::Struct),
-                    "switch" => Token::Keyword(Keyword::Switch),
-                    "type" => Token::Keyword(Keyword::Type),
-                    "var" => Token::Keyword(Keyword::Var),
-
-                    // `ident` is not a keyword.
-                    // XXX(perf): unnecessary alloc.
-                    _ => Token::Ident(ident.into()),
-                }
-            }
-            c if c.is_whitespace() => {
-                println!("ws start c: {}", c);
-                // XXX: this loop pattern is not pretty.
-                loop {
-                    if let Some(c) = self.current_char {
-                        println!("ws c: {}", c);
-                        if c.is_whitespace() {
+                    match self.current_char {
+                        Some('+') => {
                             self.bump();
-                        } else {
-                            break;
+                            Token::Increment
                         }
-                    } else {
-                        break;
+                        Some('=') => {
+                            self.bump();
+                            Token::PlusEquals
+                        }
+                        _ => Token::Plus,
                     }
                 }
-                Token::Whitespace
+                '-' => {
+                    self.bump();
 
-            }
-            '"' => {
-                self.bump();
-                let start = self.pos;
+                    match self.current_char {
+                        Some('-') => {
+                            self.bump();
+                            Token::Decrement
+                        }
+                        Some('=') => {
+                            self.bump();
+                            Token::MinusEquals
+                        }
+                        _ => Token::Minus,
+                    }
+                }
+                '|' => {
+                    self.bump();
 
-                while let Some(c) = self.current_char {
-                    // FIXME: backslash
-                    if c != '"' {
-                        self.bump();
-                    } else {
-                        break;
+                    match self.current_char {
+                        Some('|') => {
+                            self.bump();
+                            Token::PipePipe
+                        }
+                        Some('=') => {
+                            self.bump();
+                            Token::PipeEquals
+                        }
+                        _ => Token::Pipe,
+                    }
+                }
+                c if c.is_digit(10) => self.scan_number(start),
+                c if can_start_identifier(c) => {
+                    let ident = self.scan_identifier(start);
+
+                    match &*ident {
+                        "break" => Token::Keyword(Keyword::Break),
+                        "case" => Token::Keyword(Keyword::Case),
+                        "chan" => Token::Keyword(Keyword::Chan),
+                        "const" => Token::Keyword(Keyword::Const),
+                        "continue" => Token::Keyword(Keyword::Continue),
+                        "default" => Token::Keyword(Keyword::Default),
+                        "defer" => Token::Keyword(Keyword::Defer),
+                        "else" => Token::Keyword(Keyword::Else),
+                        "fallthrough" => Token::Keyword(Keyword::Fallthrough),
+                        "for" => Token::Keyword(Keyword::For),
+                        "func" => Token::Keyword(Keyword::Func),
+                        "go" => Token::Keyword(Keyword::Go),
+                        "goto" => Token::Keyword(Keyword::Goto),
+                        "if" => Token::Keyword(Keyword::If),
+                        "import" => Token::Keyword(Keyword::Import),
+                        "interface" => Token::Keyword(Keyword::Interface),
+                        "map" => Token::Keyword(Keyword::Map),
+                        "package" => Token::Keyword(Keyword::Package),
+                        "range" => Token::Keyword(Keyword::Range),
+                        "return" => Token::Keyword(Keyword::Return),
+                        "select" => Token::Keyword(Keyword::Select),
+                        "struct" => Token::Keyword(Keyword::Struct),
+                        "switch" => Token::Keyword(Keyword::Switch),
+                        "type" => Token::Keyword(Keyword::Type),
+                        "var" => Token::Keyword(Keyword::Var),
+
+                        // `ident` is not a keyword.
+                        _ => Token::Ident(ident),
                     }
                 }
+                '"' => self.scan_interpreted_string(start),
+                '`' => self.scan_raw_string(start),
+                '\'' => self.scan_rune(start),
+                c => {
+                    // Recover instead of panicking: record the error, skip the offending byte, and
+                    // let the loop above try scanning a token again.
+                    self.errors.push(LexError::new(LexErrorKind::UnexpectedChar(c),
+                                                     Span::new(start, start + c.len_utf8())));
+                    self.bump();
+                    continue;
+                }
+            };
 
-                let s = &self.src[start..self.pos];
+            self.last_significant = Some(tok.clone());
+            self.last_span = Span::new(start, self.pos);
+            return Some(tok);
+        }
+    }
+}
+
+impl<'src> Lexer<'src> {
+    /// Adapt this lexer into an iterator of `Spanned<Token>`, pairing each token with the byte
+    /// span it was scanned from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rgo::lexer::{Lexer, Token, DelimToken};
+    ///
+    /// let mut tokens = Lexer::new(")").spanned();
+    /// let spanned = tokens.next().unwrap();
+    /// assert_eq!(spanned.node, Token::CloseDelim(DelimToken::Paren));
+    /// assert_eq!((spanned.span.lo, spanned.span.hi), (0, 1));
+    /// ```
+    pub fn spanned(self) -> SpannedLexer<'src> {
+        SpannedLexer { lexer: self }
+    }
+}
+
+/// Iterator adaptor, produced by `Lexer::spanned`, that pairs each token with its `Span`.
+pub struct SpannedLexer<'src> {
+    lexer: Lexer<'src>,
+}
 
-                // Skip the quote.
+impl<'src> Iterator for SpannedLexer<'src> {
+    type Item = Spanned<Token>;
+
+    fn next(&mut self) -> Option<Spanned<Token>> {
+        match self.lexer.next() {
+            Some(tok) => Some(Spanned::new(tok, self.lexer.last_span)),
+            None => None,
+        }
+    }
+}
+
+impl<'src> Lexer<'src> {
+    /// Consume a run of whitespace, inserting a `Token::Semicolon` if a `'\n'` was crossed and
+    /// the last significant token is one after which Go's automatic semicolon insertion rule
+    /// applies (see the grammar comments in `ast` for the productions this feeds).
+    ///
+    /// Returns `None` (and leaves `self.current_char` at the next non-whitespace character, or
+    /// `None` at EOF) when no semicolon should be inserted.
+    fn skip_whitespace(&mut self) -> Option<Token> {
+        let mut saw_newline = false;
+
+        while let Some(c) = self.current_char {
+            if c.is_whitespace() {
+                saw_newline = saw_newline || c == '\n';
                 self.bump();
-                // XXX(perf): alloc.
-                Token::Literal(Literal::Str(s.into()))
+            } else {
+                break;
             }
-            c => panic!("unexpected start of token: '{}'", c),
-        };
+        }
+
+        if saw_newline && ends_statement(&self.last_significant) {
+            self.last_significant = Some(Token::Semicolon);
+            Some(Token::Semicolon)
+        } else {
+            None
+        }
+    }
+}
 
-        Some(tok)
+/// Whether `tok` is one of the token kinds after which Go inserts a semicolon when a newline
+/// (or, for `'}'`, the next token) follows: identifiers, literals, `break`/`continue`/
+/// `fallthrough`/`return`, `++`/`--`, and a closing `)`, `]`, or `}`.
+fn ends_statement(tok: &Option<Token>) -> bool {
+    match *tok {
+        Some(Token::Ident(_)) |
+        Some(Token::Literal(_)) |
+        Some(Token::Keyword(Keyword::Break)) |
+        Some(Token::Keyword(Keyword::Continue)) |
+        Some(Token::Keyword(Keyword::Fallthrough)) |
+        Some(Token::Keyword(Keyword::Return)) |
+        Some(Token::Increment) |
+        Some(Token::Decrement) |
+        Some(Token::CloseDelim(_)) => true,
+        _ => false,
     }
 }
 
@@ -273,7 +794,8 @@ impl<'src> Iterator for Lexer<'src> {
 ///
 /// assert_eq!(tokenize("()"), vec![
 ///     Token::OpenDelim(DelimToken::Paren),
-///     Token::CloseDelim(DelimToken::Paren)
+///     Token::CloseDelim(DelimToken::Paren),
+///     Token::Semicolon, // ASI fires at EOF, right after a closing ')'
 /// ]);
 /// ```
 pub fn tokenize(s: &str) -> Vec<Token> {
@@ -0,0 +1,96 @@
+use super::*;
+
+fn tokens(src: &str) -> Vec<Token> {
+    Lexer::new(src).collect()
+}
+
+#[test]
+fn bump_does_not_panic_on_multibyte_chars() {
+    // Every one of these is followed by more source, so `bump` has to land `pos` back on a
+    // UTF-8 boundary or the next slice into `src` panics.
+    assert_eq!(tokens("é+1"),
+               vec![Token::Ident("é".into()),
+                    Token::Plus,
+                    Token::Literal(Literal::Int("1".into())),
+                    Token::Semicolon]);
+}
+
+#[test]
+fn eof_inserts_a_final_semicolon() {
+    // No trailing newline: ASI still has to fire at end of input, the same as it would on a
+    // crossed '\n'.
+    assert_eq!(tokens("x"), vec![Token::Ident("x".into()), Token::Semicolon]);
+}
+
+#[test]
+fn eof_does_not_insert_a_semicolon_after_an_operator() {
+    // '+' isn't one of the token kinds ASI fires after -- see `ends_statement`.
+    assert_eq!(tokens("x +"), vec![Token::Ident("x".into()), Token::Plus]);
+}
+
+#[test]
+fn closing_brace_gets_its_own_semicolon_inserted() {
+    // One semicolon before the '}' (ASI after the identifier), and another at EOF (ASI after
+    // the '}' itself).
+    assert_eq!(tokens("{x}"),
+               vec![Token::OpenDelim(DelimToken::Brace),
+                    Token::Ident("x".into()),
+                    Token::Semicolon,
+                    Token::CloseDelim(DelimToken::Brace),
+                    Token::Semicolon]);
+}
+
+#[test]
+fn consecutive_byte_escapes_combine_into_one_utf8_sequence() {
+    // 0xc3 0xa9 is 'é' in UTF-8 -- decoding each \x byte independently would instead produce
+    // the two unrelated code points U+00C3 and U+00A9.
+    assert_eq!(tokens("\"\\xc3\\xa9\""),
+               vec![Token::Literal(Literal::Str("é".into())), Token::Semicolon]);
+}
+
+#[test]
+fn consecutive_octal_escapes_combine_into_one_utf8_sequence() {
+    assert_eq!(tokens("\"\\303\\251\""),
+               vec![Token::Literal(Literal::Str("é".into())), Token::Semicolon]);
+}
+
+#[test]
+fn a_byte_escape_run_is_broken_by_an_ordinary_character() {
+    assert_eq!(tokens("\"\\x41x\\x42\""),
+               vec![Token::Literal(Literal::Str("AxB".into())), Token::Semicolon]);
+}
+
+#[test]
+fn unicode_escapes_decode_directly_as_code_points() {
+    assert_eq!(tokens("\"\\u00e9\""),
+               vec![Token::Literal(Literal::Str("é".into())), Token::Semicolon]);
+}
+
+#[test]
+fn byte_escape_in_a_rune_literal_is_always_a_single_char() {
+    // Unlike in a string, there's nothing to combine with -- a byte value 0-255 is always a
+    // valid standalone Unicode scalar.
+    assert_eq!(tokens("'\\xff'"),
+               vec![Token::Literal(Literal::Rune('\u{ff}')), Token::Semicolon]);
+}
+
+#[test]
+fn raw_strings_keep_newlines_and_skip_escapes() {
+    assert_eq!(tokens("`a\\nb\nc`"),
+               vec![Token::Literal(Literal::Str("a\\nb\nc".into())), Token::Semicolon]);
+}
+
+#[test]
+fn scan_identifier_matches_keywords_separately_from_plain_idents() {
+    assert_eq!(tokens("func foo"),
+               vec![Token::Keyword(Keyword::Func), Token::Ident("foo".into()), Token::Semicolon]);
+}
+
+#[test]
+fn an_unexpected_character_is_recovered_from_instead_of_panicking() {
+    let (toks, errors) = Lexer::new("x $ y").into_results();
+    assert_eq!(toks.iter().map(|t| t.node.clone()).collect::<Vec<_>>(),
+               vec![Token::Ident("x".into()), Token::Ident("y".into()), Token::Semicolon]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, LexErrorKind::UnexpectedChar('$'));
+}
@@ -0,0 +1,89 @@
+//! Token definitions produced by the `Lexer`.
+
+/// A single lexical token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Ident(String),
+    Keyword(Keyword),
+    Literal(Literal),
+
+    OpenDelim(DelimToken),
+    CloseDelim(DelimToken),
+
+    Comma,
+    Dot,
+    /// `...`
+    Ellipsis,
+
+    Plus,
+    PlusEquals,
+    Increment,
+
+    Minus,
+    MinusEquals,
+    Decrement,
+
+    Pipe,
+    PipeEquals,
+    PipePipe,
+
+    /// An explicit or automatically inserted `;`.
+    Semicolon,
+
+    Whitespace,
+}
+
+/// A bracket-like delimiter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelimToken {
+    /// `(` or `)`
+    Paren,
+    /// `{` or `}`
+    Brace,
+    /// `[` or `]`
+    Bracket,
+}
+
+/// Reserved words of the Go language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keyword {
+    Break,
+    Case,
+    Chan,
+    Const,
+    Continue,
+    Default,
+    Defer,
+    Else,
+    Fallthrough,
+    For,
+    Func,
+    Go,
+    Goto,
+    If,
+    Import,
+    Interface,
+    Map,
+    Package,
+    Range,
+    Return,
+    Select,
+    Struct,
+    Switch,
+    Type,
+    Var,
+}
+
+/// A literal value as written in the source.
+///
+/// Numbers are kept as their original source text rather than parsed into `i64`/`f64`: that
+/// preserves the radix (`0x..`/`0o..`/`0b..`) and avoids baking a particular integer/float width
+/// into the lexer, at the cost of leaving the actual parsing to a later phase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Literal {
+    Str(String),
+    Rune(char),
+    Int(String),
+    Float(String),
+    Imaginary(String),
+}
@@ -19,7 +19,7 @@ extern crate lazy_static;
 extern crate quick_error;
 
 mod pos;
-pub use self::pos::Position;
+pub use self::pos::{Position, SourceMap, Span, Spanned};
 
 pub mod token;
 pub mod ast;
@@ -29,6 +29,7 @@ pub mod parser;
 pub use parser::Parser;
 
 pub fn parse(src: &str) -> ast::SourceFile {
-    let lexer = lexer::Lexer::new(src).collect();
-    parser::parse_tokens(lexer)
+    let tokens: Vec<_> = lexer::Lexer::new(src).spanned().collect();
+    let (source_file, _errors) = parser::parse_tokens(tokens);
+    source_file
 }
@@ -0,0 +1,189 @@
+//! A small parser-combinator engine over a `&[Spanned<Token>]` slice, in the style of chumsky.
+//!
+//! Primitive matchers (`just`, `ident`, `literal`) match a single token; combinators (`then`,
+//! `or`, `repeated`, `delimited_by`, `separated_by`) build them up into bigger grammars;
+//! `recover_with` lets a failing parser skip to a synchronization point instead of aborting the
+//! whole parse. See the productions in the `parser` module for how these are put together.
+
+use lexer::{Literal, Token};
+use pos::{Span, Spanned};
+
+use super::ParseError;
+
+/// A parser: given the remaining input, either consumes some prefix of it and returns a value
+/// plus whatever is left, or fails with a `ParseError` (having consumed nothing).
+pub type PResult<'t, O> = Result<(O, &'t [Spanned<Token>]), ParseError>;
+
+/// A boxed parser, so combinators can be composed and stored without naming their (otherwise
+/// unnameable) closure types.
+pub type BoxParser<'t, O> = Box<dyn Fn(&'t [Spanned<Token>]) -> PResult<'t, O> + 't>;
+
+fn unexpected<'t, O>(input: &'t [Spanned<Token>], expected: &str) -> PResult<'t, O> {
+    match input.first() {
+        Some(t) => Err(ParseError::unexpected(t.span, vec![expected.into()], Some(t.node.clone()))),
+        None => Err(ParseError::unexpected(Span::empty(0), vec![expected.into()], None)),
+    }
+}
+
+/// Match a single, specific token.
+///
+/// # Example
+///
+/// ```
+/// use rgo::lexer::{Lexer, Token, DelimToken};
+/// use rgo::parser::combinator::just;
+///
+/// let tokens: Vec<_> = Lexer::new("(").spanned().collect();
+/// assert!(just(Token::OpenDelim(DelimToken::Paren))(&tokens).is_ok());
+/// ```
+pub fn just<'t>(tok: Token) -> BoxParser<'t, Spanned<Token>> {
+    Box::new(move |input| match input.first() {
+        Some(t) if t.node == tok => Ok((t.clone(), &input[1..])),
+        _ => unexpected(input, &format!("{:?}", tok)),
+    })
+}
+
+/// Match an identifier, returning its name.
+pub fn ident<'t>() -> BoxParser<'t, Spanned<String>> {
+    Box::new(|input| match input.first() {
+        Some(t) => {
+            match t.node {
+                Token::Ident(ref name) => Ok((Spanned::new(name.clone(), t.span), &input[1..])),
+                _ => unexpected(input, "an identifier"),
+            }
+        }
+        None => unexpected(input, "an identifier"),
+    })
+}
+
+/// Match a literal.
+pub fn literal<'t>() -> BoxParser<'t, Spanned<Literal>> {
+    Box::new(|input| match input.first() {
+        Some(t) => {
+            match t.node {
+                Token::Literal(ref lit) => Ok((Spanned::new(lit.clone(), t.span), &input[1..])),
+                _ => unexpected(input, "a literal"),
+            }
+        }
+        None => unexpected(input, "a literal"),
+    })
+}
+
+/// Run `a`, then `b` on what's left, returning both results.
+pub fn then<'t, O1, O2>(a: BoxParser<'t, O1>, b: BoxParser<'t, O2>) -> BoxParser<'t, (O1, O2)>
+    where O1: 't,
+          O2: 't
+{
+    Box::new(move |input| {
+        let (v1, rest) = a(input)?;
+        let (v2, rest) = b(rest)?;
+        Ok(((v1, v2), rest))
+    })
+}
+
+/// Try `a`; if it fails, try `b` on the same input (`a`'s error is discarded -- this is plain
+/// backtracking, not error recovery; see `recover_with` for that).
+pub fn or<'t, O>(a: BoxParser<'t, O>, b: BoxParser<'t, O>) -> BoxParser<'t, O>
+    where O: 't
+{
+    Box::new(move |input| a(input).or_else(|_| b(input)))
+}
+
+/// Run `item` as many times as it keeps succeeding (zero or more), collecting the results.
+/// Never fails itself.
+pub fn repeated<'t, O>(item: BoxParser<'t, O>) -> BoxParser<'t, Vec<O>>
+    where O: 't
+{
+    Box::new(move |mut input| {
+        let mut out = Vec::new();
+        while let Ok((v, rest)) = item(input) {
+            out.push(v);
+            input = rest;
+        }
+        Ok((out, input))
+    })
+}
+
+/// Match `left`, then `inner`, then `right`, keeping only `inner`'s value.
+pub fn delimited_by<'t, OL, O, OR>(left: BoxParser<'t, OL>,
+                                    inner: BoxParser<'t, O>,
+                                    right: BoxParser<'t, OR>)
+                                    -> BoxParser<'t, O>
+    where OL: 't,
+          O: 't,
+          OR: 't
+{
+    Box::new(move |input| {
+        let (_, rest) = left(input)?;
+        let (v, rest) = inner(rest)?;
+        let (_, rest) = right(rest)?;
+        Ok((v, rest))
+    })
+}
+
+/// Match zero or more `item`s, separated by `sep` (with no trailing separator).
+pub fn separated_by<'t, O, OS>(item: BoxParser<'t, O>, sep: BoxParser<'t, OS>) -> BoxParser<'t, Vec<O>>
+    where O: 't,
+          OS: 't
+{
+    Box::new(move |input| {
+        let mut out = Vec::new();
+
+        let mut rest = match item(input) {
+            Ok((v, rest)) => {
+                out.push(v);
+                rest
+            }
+            Err(_) => return Ok((out, input)),
+        };
+
+        loop {
+            let after_sep = match sep(rest) {
+                Ok((_, after_sep)) => after_sep,
+                Err(_) => break,
+            };
+
+            match item(after_sep) {
+                Ok((v, after_item)) => {
+                    out.push(v);
+                    rest = after_item;
+                }
+                Err(_) => break,
+            }
+        }
+
+        Ok((out, rest))
+    })
+}
+
+/// Run `item`. If it fails, record the error onto `errors` and skip tokens -- without consuming
+/// one that matches `is_sync` -- so the caller can resynchronize there and keep parsing past the
+/// malformed construct, instead of aborting the whole parse.
+///
+/// `repeated`/`separated_by` above don't attempt recovery -- a single failing item there just
+/// ends the repetition -- so productions that need recovery (e.g. a malformed spec inside
+/// `import ( ... )`) call this directly instead.
+pub fn recover_with<'t, O, F>(item: &BoxParser<'t, O>,
+                               input: &'t [Spanned<Token>],
+                               is_sync: F,
+                               errors: &mut Vec<ParseError>)
+                               -> (Option<O>, &'t [Spanned<Token>])
+    where F: Fn(&Token) -> bool
+{
+    match item(input) {
+        Ok((v, rest)) => (Some(v), rest),
+        Err(e) => {
+            errors.push(e);
+
+            let mut rest = input;
+            while let Some(t) = rest.first() {
+                if is_sync(&t.node) {
+                    break;
+                }
+                rest = &rest[1..];
+            }
+
+            (None, rest)
+        }
+    }
+}
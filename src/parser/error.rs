@@ -0,0 +1,33 @@
+//! Parse errors: "expected X, found Y" with enough information to point at the source.
+
+use lexer::Token;
+use pos::Span;
+
+/// A parse error: none of `expected` matched at `span`; `found` is what was actually there (or
+/// `None` at end of file).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Span,
+    pub expected: Vec<String>,
+    pub found: Option<Token>,
+}
+
+impl ParseError {
+    pub fn unexpected(span: Span, expected: Vec<String>, found: Option<Token>) -> ParseError {
+        ParseError {
+            span: span,
+            expected: expected,
+            found: found,
+        }
+    }
+}
+
+impl ::std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        let expected = self.expected.join(" or ");
+        match self.found {
+            Some(ref tok) => write!(f, "expected {}, found {:?}", expected, tok),
+            None => write!(f, "expected {}, found end of file", expected),
+        }
+    }
+}
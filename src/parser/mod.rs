@@ -0,0 +1,393 @@
+//! The parser: turns a token stream into an `ast::SourceFile`, recovering from malformed
+//! declarations instead of aborting on the first one.
+//!
+//! Built on top of a small parser-combinator engine (`combinator`), chumsky-style: grammar
+//! productions are assembled from `just`/`ident` and combinators like `then`/`or`/
+//! `separated_by`/`delimited_by`/`repeated` -- see `package_clause`, `import_spec`,
+//! `parameter_list` and `skip_semicolons` for examples of each. Recovery goes through `combinator::recover_with`,
+//! which skips to a synchronization token (`;` or a closing delimiter) instead of giving up on
+//! the rest of the file; `source_file` below does the same thing by hand between top-level
+//! declarations (and `import_decl` between import specs), since neither `repeated` nor
+//! `separated_by` attempt recovery on their own -- a single malformed item there would just end
+//! the repetition early rather than skip past it.
+//!
+//! The type grammar only covers plain (possibly package-qualified) identifiers so far
+//! (`plain_type`) -- composite types (`[]int`, `*T`, ...) fall back to `skip_balanced`, and
+//! function bodies are skipped wholesale, since `ast::Statement` is still an empty stub.
+
+pub mod combinator;
+
+mod error;
+pub use self::error::*;
+
+use ast::{FuncDecl, FuncSignature, ImportDecl, ImportKind, ImportSpec, MaybeQualifiedIdent,
+          Parameters, ParameterDecl, SourceFile, TopLevelDecl, Type};
+use lexer::{DelimToken, Keyword, Literal, Token};
+use pos::{Span, Spanned};
+
+use self::combinator::{delimited_by, just, or, recover_with, repeated, separated_by, then,
+                        BoxParser, PResult};
+
+fn keyword<'t>(kw: Keyword) -> BoxParser<'t, Spanned<Token>> {
+    just(Token::Keyword(kw))
+}
+
+fn package_clause<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, (String, Span)> {
+    let ((pkg_tok, name), rest) = then(keyword(Keyword::Package), combinator::ident())(input)?;
+    Ok(((name.node, pkg_tok.span.to(name.span)), rest))
+}
+
+fn import_path<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, Spanned<String>> {
+    match input.first() {
+        Some(t) => {
+            match t.node {
+                Token::Literal(Literal::Str(ref s)) => Ok((Spanned::new(s.clone(), t.span), &input[1..])),
+                _ => Err(ParseError::unexpected(t.span, vec!["an import path".into()], Some(t.node.clone()))),
+            }
+        }
+        None => Err(ParseError::unexpected(Span::empty(0), vec!["an import path".into()], None)),
+    }
+}
+
+fn glob_import_spec<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, ImportSpec> {
+    let (dot, rest) = just(Token::Dot)(input)?;
+    let (path, rest) = import_path(rest)?;
+    Ok((ImportSpec {
+                kind: ImportKind::Glob,
+                path: path.node,
+                span: dot.span.to(path.span),
+            },
+            rest))
+}
+
+fn aliased_import_spec<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, ImportSpec> {
+    let (name, rest) = combinator::ident()(input)?;
+    let (path, rest) = import_path(rest)?;
+    Ok((ImportSpec {
+                kind: ImportKind::Alias(name.node),
+                path: path.node,
+                span: name.span.to(path.span),
+            },
+            rest))
+}
+
+fn plain_import_spec<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, ImportSpec> {
+    let (path, rest) = import_path(input)?;
+    Ok((ImportSpec {
+                kind: ImportKind::Normal,
+                path: path.node,
+                span: path.span,
+            },
+            rest))
+}
+
+/// ImportSpec = [ "." | PackageName ] ImportPath .
+fn import_spec<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, ImportSpec> {
+    let glob: BoxParser<ImportSpec> = Box::new(glob_import_spec);
+    let aliased: BoxParser<ImportSpec> = Box::new(aliased_import_spec);
+    let plain: BoxParser<ImportSpec> = Box::new(plain_import_spec);
+
+    or(or(glob, aliased), plain)(input)
+}
+
+fn is_semicolon(tok: &Token) -> bool {
+    *tok == Token::Semicolon
+}
+
+fn is_import_spec_sync(tok: &Token) -> bool {
+    is_semicolon(tok) || *tok == Token::CloseDelim(DelimToken::Paren)
+}
+
+/// ImportDecl = "import" ( ImportSpec | "(" { ImportSpec ";" } ")" ) .
+///
+/// Each spec inside the parenthesized form is parsed through `recover_with`: a malformed spec is
+/// recorded onto `errors` and skipped up to its trailing `;` (or the closing `)`), so one bad
+/// line doesn't take the rest of the import block down with it.
+fn import_decl<'t>(input: &'t [Spanned<Token>], errors: &mut Vec<ParseError>) -> PResult<'t, ImportDecl> {
+    let (import_tok, rest) = keyword(Keyword::Import)(input)?;
+
+    match rest.first() {
+        Some(t) if t.node == Token::OpenDelim(DelimToken::Paren) => {
+            let mut specs = Vec::new();
+            let spec_parser: BoxParser<ImportSpec> = Box::new(import_spec);
+            let mut rest = &rest[1..];
+
+            while let Some(t) = rest.first() {
+                if t.node == Token::CloseDelim(DelimToken::Paren) {
+                    break;
+                }
+
+                let (spec, after) = recover_with(&spec_parser, rest, is_import_spec_sync, errors);
+                specs.extend(spec);
+                rest = after;
+
+                match rest.first() {
+                    Some(t) if t.node == Token::Semicolon => rest = &rest[1..],
+                    _ => break,
+                }
+            }
+
+            let (close_tok, rest) = just(Token::CloseDelim(DelimToken::Paren))(rest)?;
+            let span = import_tok.span.to(close_tok.span);
+            Ok((ImportDecl {
+                        specs: specs,
+                        span: span,
+                    },
+                    rest))
+        }
+        _ => {
+            let (spec, rest) = import_spec(rest)?;
+            let span = import_tok.span.to(spec.span);
+            Ok((ImportDecl {
+                        specs: vec![spec],
+                        span: span,
+                    },
+                    rest))
+        }
+    }
+}
+
+/// Consume a balanced `delim`-delimited group without interpreting its contents, returning the
+/// span it occupies. Used where the grammar for what's inside isn't wired up to the token stream
+/// yet (see the module doc comment).
+fn skip_balanced<'t>(input: &'t [Spanned<Token>], delim: DelimToken) -> PResult<'t, Span> {
+    let (open_tok, mut rest) = just(Token::OpenDelim(delim))(input)?;
+    let mut depth = 1;
+    let mut last_span = open_tok.span;
+
+    loop {
+        match rest.first() {
+            Some(t) => {
+                last_span = t.span;
+                rest = &rest[1..];
+                match t.node {
+                    Token::OpenDelim(d) if d == delim => depth += 1,
+                    Token::CloseDelim(d) if d == delim => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None => {
+                return Err(ParseError::unexpected(last_span, vec![format!("closing {:?}", delim)], None));
+            }
+        }
+    }
+
+    Ok((open_tok.span.to(last_span), rest))
+}
+
+/// TypeName = identifier | QualifiedIdent .
+/// QualifiedIdent = PackageName "." identifier .
+///
+/// XXX: only plain (possibly qualified) identifiers are handled -- composite types (`[]int`,
+/// `*T`, `struct { ... }`, ...) aren't parsed into `ast::TypeLiteral` yet, so a parameter using
+/// one falls back to `skip_balanced` in `parameter_list_or_skip` below.
+fn maybe_qualified_ident<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, MaybeQualifiedIdent> {
+    let (first, rest) = combinator::ident()(input)?;
+
+    match rest.first() {
+        Some(t) if t.node == Token::Dot => {
+            let (second, rest) = combinator::ident()(&rest[1..])?;
+            Ok((MaybeQualifiedIdent {
+                        package: Some(first.node),
+                        name: second.node,
+                    },
+                    rest))
+        }
+        _ => {
+            Ok((MaybeQualifiedIdent {
+                        package: None,
+                        name: first.node,
+                    },
+                    rest))
+        }
+    }
+}
+
+fn plain_type<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, Type> {
+    let (ident, rest) = maybe_qualified_ident(input)?;
+    Ok((Type::Plain(ident), rest))
+}
+
+/// ParameterDecl = IdentifierList Type .
+fn parameter_decl<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, ParameterDecl> {
+    let (names, rest) = separated_by(combinator::ident(), just(Token::Comma))(input)?;
+
+    if names.is_empty() {
+        return match rest.first() {
+            Some(t) => Err(ParseError::unexpected(t.span, vec!["a parameter name".into()], Some(t.node.clone()))),
+            None => Err(ParseError::unexpected(Span::empty(0), vec!["a parameter name".into()], None)),
+        };
+    }
+
+    let (typ, rest) = plain_type(rest)?;
+    let identifiers = names.into_iter().map(|n| n.node).collect();
+    Ok((ParameterDecl::new(identifiers, typ), rest))
+}
+
+/// Parameters = "(" [ ParameterDecl { "," ParameterDecl } ] ")" .
+fn parameter_list<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, Parameters> {
+    let decls: BoxParser<Vec<ParameterDecl>> =
+        Box::new(|input| separated_by(Box::new(parameter_decl), just(Token::Comma))(input));
+
+    delimited_by(just(Token::OpenDelim(DelimToken::Paren)),
+                 decls,
+                 just(Token::CloseDelim(DelimToken::Paren)))(input)
+        .map(|(decls, rest)| (Parameters::from_decls(decls), rest))
+}
+
+/// Like `parameter_list`, but falls back to skipping the parenthesized group unparsed if it
+/// contains a parameter type the grammar doesn't cover yet (see `plain_type`).
+fn parameter_list_or_skip<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, Parameters> {
+    let parsed: BoxParser<Parameters> = Box::new(parameter_list);
+    let skipped: BoxParser<Parameters> = Box::new(|input| {
+        let (_, rest) = skip_balanced(input, DelimToken::Paren)?;
+        Ok((Parameters::empty(), rest))
+    });
+
+    or(parsed, skipped)(input)
+}
+
+/// FunctionDecl = "func" FunctionName Signature FunctionBody .
+///
+/// XXX: the result type is always empty and the body is skipped wholesale, since `ast::Statement`
+/// is still an empty stub -- see `parameter_list_or_skip` for how much of the parameter list is
+/// actually parsed.
+fn func_decl<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, FuncDecl> {
+    let (func_tok, rest) = keyword(Keyword::Func)(input)?;
+    let (name, rest) = combinator::ident()(rest)?;
+    let (parameters, rest) = parameter_list_or_skip(rest)?;
+    let (body_span, rest) = skip_balanced(rest, DelimToken::Brace)?;
+
+    let decl = FuncDecl {
+        name: name.node,
+        signature: FuncSignature {
+            parameters: parameters,
+            result: Parameters::empty(),
+        },
+        body: Vec::new(),
+        span: func_tok.span.to(body_span),
+    };
+
+    Ok((decl, rest))
+}
+
+fn top_level_decl<'t>(input: &'t [Spanned<Token>]) -> PResult<'t, TopLevelDecl> {
+    match input.first() {
+        Some(t) if t.node == Token::Keyword(Keyword::Func) => {
+            let (decl, rest) = func_decl(input)?;
+            Ok((TopLevelDecl::Func(decl), rest))
+        }
+        Some(t) => Err(ParseError::unexpected(t.span, vec!["a top-level declaration".into()], Some(t.node.clone()))),
+        None => Err(ParseError::unexpected(Span::empty(0), vec!["a top-level declaration".into()], None)),
+    }
+}
+
+/// Skip zero or more `;`s in a row (ASI can legitimately produce more than one in a row around
+/// empty statements).
+fn skip_semicolons<'t>(input: &'t [Spanned<Token>]) -> &'t [Spanned<Token>] {
+    let (_, rest) = repeated(just(Token::Semicolon))(input).unwrap();
+    rest
+}
+
+/// Skip past the next `;`, or to end of input if there isn't one; used to resynchronize after a
+/// top-level declaration we couldn't parse at all.
+fn resync_to_semicolon<'t>(input: &'t [Spanned<Token>]) -> &'t [Spanned<Token>] {
+    let mut rest = input;
+    while let Some(t) = rest.first() {
+        rest = &rest[1..];
+        if is_semicolon(&t.node) {
+            break;
+        }
+    }
+    rest
+}
+
+/// SourceFile = PackageClause ";" { ImportDecl ";" } { TopLevelDecl ";" } .
+fn source_file(tokens: &[Spanned<Token>]) -> (SourceFile, Vec<ParseError>) {
+    let mut errors = Vec::new();
+    let start = tokens.first().map(|t| t.span).unwrap_or_else(|| Span::empty(0));
+
+    let (package, mut rest) = match package_clause(tokens) {
+        Ok(((name, _), rest)) => (name, rest),
+        Err(e) => {
+            errors.push(e);
+            (String::new(), tokens)
+        }
+    };
+    rest = skip_semicolons(rest);
+
+    let mut import_decls = Vec::new();
+    while let Some(t) = rest.first() {
+        if t.node != Token::Keyword(Keyword::Import) {
+            break;
+        }
+
+        match import_decl(rest, &mut errors) {
+            Ok((decl, after)) => {
+                import_decls.push(decl);
+                rest = skip_semicolons(after);
+            }
+            Err(e) => {
+                errors.push(e);
+                rest = resync_to_semicolon(rest);
+            }
+        }
+    }
+
+    let mut top_level_decls = Vec::new();
+    while !rest.is_empty() {
+        match top_level_decl(rest) {
+            Ok((decl, after)) => {
+                top_level_decls.push(decl);
+                rest = skip_semicolons(after);
+            }
+            Err(e) => {
+                errors.push(e);
+                rest = resync_to_semicolon(rest);
+            }
+        }
+    }
+
+    // `rest` is always a suffix of `tokens` at this point (the loops above only ever narrow it
+    // from the front), so the span of whatever we last consumed is the token just before wherever
+    // `rest` currently starts -- `rest.first()` would instead (almost always wrongly) give the
+    // span of whatever comes *after* the parsed content, which is nothing once `rest` is empty.
+    let end = if rest.len() < tokens.len() {
+        tokens[tokens.len() - rest.len() - 1].span
+    } else {
+        start
+    };
+    let file = SourceFile {
+        package: package,
+        import_decls: import_decls,
+        top_level_decls: top_level_decls,
+        span: start.to(end),
+    };
+
+    (file, errors)
+}
+
+/// Parse a whole token stream into a `SourceFile`, recovering from malformed declarations along
+/// the way. Returns every error encountered, not just the first.
+pub fn parse_tokens(tokens: Vec<Spanned<Token>>) -> (SourceFile, Vec<ParseError>) {
+    source_file(&tokens)
+}
+
+/// Stateless convenience wrapper around `parse_tokens`, for callers that want a type to hold
+/// onto rather than a bare function.
+pub struct Parser;
+
+impl Parser {
+    pub fn new() -> Parser {
+        Parser
+    }
+
+    pub fn parse(&self, tokens: &[Spanned<Token>]) -> (SourceFile, Vec<ParseError>) {
+        source_file(tokens)
+    }
+}
@@ -0,0 +1,89 @@
+//! Source positions: byte offsets, human-readable line/column coordinates, and the machinery to
+//! convert between the two.
+
+/// A human-readable source location: 1-based line and column numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A half-open range of byte offsets `[lo, hi)` into a source string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub lo: usize,
+    pub hi: usize,
+}
+
+impl Span {
+    pub fn new(lo: usize, hi: usize) -> Span {
+        Span { lo: lo, hi: hi }
+    }
+
+    /// A zero-width span at `pos`, used for e.g. an automatically inserted semicolon.
+    pub fn empty(pos: usize) -> Span {
+        Span::new(pos, pos)
+    }
+
+    /// The smallest span covering both `self` and `other`.
+    pub fn to(&self, other: Span) -> Span {
+        Span::new(::std::cmp::min(self.lo, other.lo),
+                   ::std::cmp::max(self.hi, other.hi))
+    }
+}
+
+/// Wraps a value of type `T` together with the span of source text it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Spanned<T> {
+        Spanned {
+            node: node,
+            span: span,
+        }
+    }
+}
+
+/// Maps byte offsets into a source string to `Position`s, by precomputing where every line
+/// starts.
+pub struct SourceMap {
+    /// Byte offset of the first character of each line; `line_starts[0]` is always `0`.
+    line_starts: Vec<usize>,
+}
+
+impl SourceMap {
+    pub fn new(src: &str) -> SourceMap {
+        let mut line_starts = vec![0];
+        for (i, b) in src.bytes().enumerate() {
+            if b == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        SourceMap { line_starts: line_starts }
+    }
+
+    /// Convert a byte offset into a 1-based `Position`, by binary-searching the precomputed
+    /// line start offsets.
+    pub fn position(&self, offset: usize) -> Position {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(exact) => exact,
+            Err(next) => next - 1,
+        };
+        let column = offset - self.line_starts[line];
+        Position {
+            line: line + 1,
+            column: column + 1,
+        }
+    }
+
+    /// The text of the given 1-based line, with its trailing newline stripped.
+    pub fn line_text<'a>(&self, src: &'a str, line: usize) -> &'a str {
+        let start = self.line_starts[line - 1];
+        let end = self.line_starts.get(line).cloned().unwrap_or_else(|| src.len());
+        src[start..end].trim_end_matches(|c| c == '\n' || c == '\r')
+    }
+}
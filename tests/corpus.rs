@@ -0,0 +1,87 @@
+//! Golden-file regression harness.
+//!
+//! Every `.go` file under `tests/corpus/` is tokenized and parsed, and the resulting token stream
+//! and AST (pretty-printed via `Debug`, alongside any parse errors) are compared against a
+//! `.snap` file of the same name. Including the token stream means a lexer-only regression (a bad
+//! escape decode, a missing ASI semicolon, ...) shows up in the diff even when it doesn't change
+//! the AST. This is the workflow swc uses with test262: drop a source file in, run once with
+//! `RGO_UPDATE_SNAPSHOTS=1` to record its snapshot, and get regression coverage of the lexer and
+//! parser without hand-writing expected `Token`/AST trees.
+
+#[macro_use]
+extern crate rgo;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rgo::lexer::Lexer;
+use rgo::parser::parse_tokens;
+
+const CORPUS_DIR: &str = "tests/corpus";
+
+fn corpus_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(CORPUS_DIR)
+        .expect("tests/corpus directory should exist")
+        .map(|entry| entry.expect("readable directory entry").path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "go"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn snapshot_path(go_file: &Path) -> PathBuf {
+    go_file.with_extension("snap")
+}
+
+fn render(src: &str) -> String {
+    let tokens: Vec<_> = Lexer::new(src).spanned().collect();
+    let (ast, errors) = parse_tokens(tokens.clone());
+    format!("tokens: {:#?}\n\n{:#?}\n\nerrors: {:#?}\n", tokens, ast, errors)
+}
+
+#[test]
+fn corpus_matches_snapshots() {
+    let update = env::var("RGO_UPDATE_SNAPSHOTS").is_ok();
+    let mut failures = Vec::new();
+
+    for go_file in corpus_files() {
+        let src = fs::read_to_string(&go_file).expect("readable corpus file");
+        let rendered = render(&src);
+        let snap_file = snapshot_path(&go_file);
+
+        if update {
+            fs::write(&snap_file, &rendered).expect("writable snapshot file");
+            continue;
+        }
+
+        let expected = fs::read_to_string(&snap_file).unwrap_or_else(|_| {
+            panic!("missing snapshot {:?} -- run with RGO_UPDATE_SNAPSHOTS=1 to create it", snap_file)
+        });
+
+        if rendered != expected {
+            failures.push(go_file);
+        }
+    }
+
+    if !failures.is_empty() {
+        panic!(
+            "{} corpus file(s) no longer match their snapshot (rerun with \
+             RGO_UPDATE_SNAPSHOTS=1 to accept): {:?}",
+            failures.len(),
+            failures
+        );
+    }
+}
+
+/// Reformatting a source file (extra blank lines, trailing whitespace) shifts every span in the
+/// parsed tree, but shouldn't change the tree itself -- exactly what `assert_eq_ignore_span!` is
+/// for.
+#[test]
+fn reformatting_does_not_change_the_ast() {
+    let compact = rgo::parse("package main\n\nfunc f(x int) {}\n");
+    let spread = rgo::parse("package main\n\n\nfunc f(x int)   {}\n\n");
+
+    assert_ne!(compact, spread, "the two files should parse to different spans");
+    assert_eq_ignore_span!(compact, spread);
+}